@@ -0,0 +1,169 @@
+use std::fmt;
+
+/// Everything that can go wrong while parsing an ELF file, from a bad
+/// magic number all the way down to an attacker-controlled offset that
+/// would otherwise read past the end of the buffer.
+#[derive(Debug)]
+pub enum Error {
+    /// Couldn't open or read the target file.
+    Io(std::io::Error),
+    /// The file doesn't start with the ELF magic bytes (`\x7fELF`).
+    NotAnElf,
+    /// The `EI_CLASS` byte wasn't `1` (32-bit) or `2` (64-bit).
+    UnsupportedClass(u8),
+    /// A read of `len` bytes starting at `offset` would run past the
+    /// end of the buffer, which is `buffer_len` bytes long.
+    OutOfBounds {
+        offset: usize,
+        len: usize,
+        buffer_len: usize,
+    },
+    /// `e_phoff` is zero, meaning the file has no program header table.
+    NoProgramHeaders,
+    /// No `PT_DYNAMIC` program header was found, so there's nothing to
+    /// walk for `DT_NEEDED` entries.
+    MissingDynamicSegment,
+    /// The dynamic section has no `DT_STRTAB` entry.
+    MissingStrTab,
+    /// The dynamic section has no `DT_STRSZ` entry.
+    MissingStrSz,
+    /// A string read out of the string table (a library name, a
+    /// `DT_RPATH`/`DT_RUNPATH` entry or a dynamic symbol name) wasn't
+    /// valid UTF-8.
+    NonUtf8LibraryName,
+    /// `/proc/<pid>/maps` has no mapping for `/proc/<pid>/exe`, so the
+    /// load base of the process's main executable couldn't be found.
+    ProcessExeNotMapped(u32),
+    /// A read out of a live process's memory asked for more than
+    /// `MAX_PROC_READ_LEN` bytes. Unlike `FileSource`, which can only
+    /// ever slice an already-resident buffer, `ProcMemSource` has to
+    /// allocate the destination buffer itself, so a corrupted/hostile
+    /// `p_filesz`/`dt_strsz` has to be rejected before the allocation
+    /// rather than after.
+    ReadTooLarge { len: usize, max: usize },
+    /// A file/link-time offset taken from the target (e.g. a segment's
+    /// `p_offset`) added to the process's load base overflowed `u64`.
+    ProcessAddressOverflow { base: u64, offset: u64 },
+    /// `e_phentsize` is smaller than the on-disk `Elf32_Phdr`/`Elf64_Phdr`
+    /// it's supposed to describe, so chunking the program header table
+    /// by it would either index past the end of each chunk or (at `0`)
+    /// panic in `[T]::chunks` itself.
+    InvalidProgramHeaderEntrySize { actual: u16, expected: u16 },
+    /// `DT_SYMENT` is smaller than the on-disk `Elf32_Sym`/`Elf64_Sym` it's
+    /// supposed to describe, for the same reason an undersized
+    /// `e_phentsize` is rejected.
+    InvalidSymbolEntrySize { actual: u64, expected: u64 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::NotAnElf => write!(f, "not an ELF file"),
+            Self::UnsupportedClass(class) => {
+                write!(f, "unsupported EI_CLASS byte: {class} (expected 1 or 2)")
+            }
+            Self::OutOfBounds {
+                offset,
+                len,
+                buffer_len,
+            } => write!(
+                f,
+                "read of {len} byte(s) at offset {offset} is out of bounds (file is {buffer_len} bytes)"
+            ),
+            Self::NoProgramHeaders => write!(f, "file has no program header table"),
+            Self::MissingDynamicSegment => write!(f, "no PT_DYNAMIC program header found"),
+            Self::MissingStrTab => write!(f, "no DT_STRTAB entry found in the dynamic section"),
+            Self::MissingStrSz => write!(f, "no DT_STRSZ entry found in the dynamic section"),
+            Self::NonUtf8LibraryName => write!(f, "string table entry is not valid UTF-8"),
+            Self::ProcessExeNotMapped(pid) => {
+                write!(f, "no mapping for /proc/{pid}/exe found in /proc/{pid}/maps")
+            }
+            Self::ReadTooLarge { len, max } => {
+                write!(f, "refusing to read {len} byte(s) from process memory (max {max})")
+            }
+            Self::ProcessAddressOverflow { base, offset } => write!(
+                f,
+                "load base {base:#x} plus offset {offset:#x} overflows an address"
+            ),
+            Self::InvalidProgramHeaderEntrySize { actual, expected } => write!(
+                f,
+                "e_phentsize is {actual}, smaller than the {expected}-byte program header it describes"
+            ),
+            Self::InvalidSymbolEntrySize { actual, expected } => write!(
+                f,
+                "DT_SYMENT is {actual}, smaller than the {expected}-byte symbol entry it describes"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// Bounds-checked equivalent of `&buffer[offset..offset + len]`.
+/// `p_offset`/`p_filesz`/string-table offsets all come straight out of
+/// the file, so every slice derived from them has to go through this
+/// instead of indexing directly.
+pub(crate) fn checked_slice(buffer: &[u8], offset: usize, len: usize) -> Result<&[u8], Error> {
+    buffer
+        .get(offset..offset.checked_add(len).ok_or(Error::OutOfBounds {
+            offset,
+            len,
+            buffer_len: buffer.len(),
+        })?)
+        .ok_or(Error::OutOfBounds {
+            offset,
+            len,
+            buffer_len: buffer.len(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checked_slice_returns_the_in_bounds_slice() {
+        let buf = [1u8, 2, 3, 4, 5];
+        assert_eq!(checked_slice(&buf, 1, 3).unwrap(), &[2, 3, 4]);
+    }
+
+    #[test]
+    fn checked_slice_allows_a_zero_len_read_at_the_end() {
+        let buf = [1u8, 2, 3];
+        assert_eq!(checked_slice(&buf, 3, 0).unwrap(), &[] as &[u8]);
+    }
+
+    #[test]
+    fn checked_slice_rejects_a_read_past_the_end() {
+        let buf = [1u8, 2, 3];
+        let err = checked_slice(&buf, 1, 10).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::OutOfBounds {
+                offset: 1,
+                len: 10,
+                buffer_len: 3
+            }
+        ));
+    }
+
+    #[test]
+    fn checked_slice_rejects_an_offset_past_the_end() {
+        let buf = [1u8, 2, 3];
+        assert!(checked_slice(&buf, 4, 0).is_err());
+    }
+
+    #[test]
+    fn checked_slice_rejects_an_offset_plus_len_overflow() {
+        let buf = [1u8, 2, 3];
+        let err = checked_slice(&buf, usize::MAX, 1).unwrap_err();
+        assert!(matches!(err, Error::OutOfBounds { .. }));
+    }
+}
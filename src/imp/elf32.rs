@@ -0,0 +1,131 @@
+use crate::error::Error;
+use crate::source::ReadAt;
+
+use super::{
+    build_dynamic_section_criticals, DynSectionElement, DynSectionTag, DynamicSectionCriticals,
+    ElfClass, Endianness, ElfProgramSection, ElfSHeaderMeta, ElfSegmentType, RawSymbol,
+};
+
+/// For the 32 Bit ELF specification the field widths shrink compared
+/// to 64 bit, see https://docs.oracle.com/cd/E23824_01/html/819-0690/chapter7-6.html
+/// Elf32_Addr | Elf32_Off = u32
+/// Elf32_Half = u16
+/// Elf32_Sword = i32
+/// Elf32_Word = u32
+/// unsigned char = u8
+///
+/// Every parsed value is widened to `u64` on the way out so the rest
+/// of the program can treat `Elf32` and `Elf64` identically.
+pub struct Elf32;
+
+impl ElfClass for Elf32 {
+    /// Extracts the section header meta
+    /// crucial for us to read the section header
+    /// e_phoff: u32 - byte 0x1c to 0x20
+    /// e_phentsize: u16 - byte 0x2a to 0x2c
+    /// e_phnum: u16 - byte 0x2c to 0x2e
+    fn extract_section_header_meta(
+        source: &dyn ReadAt,
+        endianness: Endianness,
+    ) -> Result<ElfSHeaderMeta, Error> {
+        let (e_phoff, e_phentsize, e_phnum) = (
+            endianness.read_u32(source.read_at(0x1c, 4)?.as_slice().try_into().unwrap()) as u64,
+            endianness.read_u16(source.read_at(0x2a, 2)?.as_slice().try_into().unwrap()),
+            endianness.read_u16(source.read_at(0x2c, 2)?.as_slice().try_into().unwrap()),
+        );
+
+        if e_phoff == 0 {
+            return Err(Error::NoProgramHeaders);
+        }
+
+        Ok(ElfSHeaderMeta {
+            e_phoff,
+            e_phentsize,
+            e_phnum,
+        })
+    }
+
+    /// Parses every program header entry contained in the ELF file
+    fn parse_program_headers(
+        source: &dyn ReadAt,
+        h_meta: &ElfSHeaderMeta,
+        endianness: Endianness,
+    ) -> Result<Vec<ElfProgramSection>, Error> {
+        // The on-disk size of one `Elf32_Phdr`. `e_phentsize` comes
+        // straight out of the file, and every entry below is decoded at
+        // fixed offsets up to this size, so an `e_phentsize` smaller
+        // than this (including `0`, which `[T]::chunks` rejects outright)
+        // has to be caught here rather than panicking on the first chunk.
+        const PROGRAM_HEADER_ENTRY_SIZE: usize = 32;
+
+        let section_offset = h_meta.e_phoff;
+        let section_size = h_meta.e_phentsize as usize;
+        if section_size < PROGRAM_HEADER_ENTRY_SIZE {
+            return Err(Error::InvalidProgramHeaderEntrySize {
+                actual: h_meta.e_phentsize,
+                expected: PROGRAM_HEADER_ENTRY_SIZE as u16,
+            });
+        }
+
+        let section_amount = h_meta.e_phnum as usize;
+        let program_sections = source.read_at(section_offset, section_size * section_amount)?;
+
+        Ok(program_sections
+            .chunks(section_size)
+            .map(|ch| ElfProgramSection {
+                p_type: ElfSegmentType::from(endianness.read_u32(ch[0x0..0x04].try_into().unwrap())),
+                p_offset: endianness.read_u32(ch[0x04..0x08].try_into().unwrap()) as u64,
+                p_filesz: endianness.read_u32(ch[0x10..0x14].try_into().unwrap()) as u64,
+            })
+            .collect())
+    }
+
+    /// Reads and extracts the dynamic section criticals
+    /// which is basically the DT_NEEDED entries and DT_STRTAB
+    /// and in chunks of 8 bytes which is the size of a 32-bit dynamic
+    /// section element (a u32 d_tag followed by a u32 d_un)
+    fn read_dynamic_section(
+        source: &dyn ReadAt,
+        dyn_meta: &ElfProgramSection,
+        endianness: Endianness,
+    ) -> Result<DynamicSectionCriticals, Error> {
+        const DYNAMIC_SECTION_ELEMENT_SIZE: usize = 8;
+
+        // p_offset/p_filesz come straight out of the program header of a
+        // file we don't control, so the read has to go through `ReadAt`
+        // (and, for a `FileSource`, `checked_slice`) rather than
+        // indexing the buffer directly.
+        let offset = dyn_meta.p_offset;
+        let size = dyn_meta.p_filesz as usize;
+        let complete_section = source.read_at(offset, size)?;
+
+        let elements = complete_section
+            .chunks(DYNAMIC_SECTION_ELEMENT_SIZE)
+            .filter(|x| x.len() == DYNAMIC_SECTION_ELEMENT_SIZE)
+            .map(|x| DynSectionElement {
+                d_tag: DynSectionTag::from(
+                    endianness.read_u32(x[0x0..0x04].try_into().unwrap()) as u64
+                ),
+                d_thing: endianness.read_u32(x[0x04..0x08].try_into().unwrap()) as u64,
+            })
+            .collect::<Vec<_>>();
+
+        build_dynamic_section_criticals(elements)
+    }
+
+    /// `Elf32_Sym` is 16 bytes: st_name(u32)@0x00, st_value(u32)@0x04,
+    /// st_size(u32)@0x08, st_info(u8)@0x0c, st_other(u8)@0x0d,
+    /// st_shndx(u16)@0x0e — a different field order than `Elf64_Sym`.
+    const SYM_ENTRY_SIZE: usize = 16;
+    const ADDR_SIZE: usize = 4;
+
+    fn parse_symbol_entry(raw: &[u8], endianness: Endianness) -> RawSymbol {
+        RawSymbol {
+            st_name: endianness.read_u32(raw[0x00..0x04].try_into().unwrap()),
+            st_value: endianness.read_u32(raw[0x04..0x08].try_into().unwrap()) as u64,
+            st_size: endianness.read_u32(raw[0x08..0x0c].try_into().unwrap()) as u64,
+            st_info: raw[0x0c],
+            st_shndx: endianness.read_u16(raw[0x0e..0x10].try_into().unwrap()),
+        }
+    }
+}
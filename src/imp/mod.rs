@@ -0,0 +1,766 @@
+pub mod elf32;
+pub mod elf64;
+
+use std::io::{BufRead, Cursor, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::error::{checked_slice, Error};
+use crate::source::{FileSource, ProcMemSource, ReadAt};
+
+pub const MAGIC_IDENT: [u8; 4] = [0x7f, 0x45, 0x4c, 0x46];
+
+/// The class/endianness-agnostic result of parsing one ELF file's
+/// dynamic section: its direct `DT_NEEDED` sonames plus its `DT_RPATH`
+/// and `DT_RUNPATH`, if present. This is what the dependency resolver
+/// walks to build the transitive closure.
+#[derive(Debug)]
+pub struct DynamicInfo {
+    pub needed: Vec<String>,
+    pub rpath: Option<String>,
+    pub runpath: Option<String>,
+    /// The GNU build-id from the `.note.gnu.build-id` note, if present,
+    /// as a lowercase hex string.
+    pub build_id: Option<String>,
+    /// Every entry of the `.dynsym` table, i.e. everything `nm -D` would
+    /// show: symbols this object imports as well as the ones it exports.
+    pub symbols: Vec<DynamicSymbol>,
+}
+
+/// Parses the ELF file at `path` by reading it fully into memory first.
+pub fn parse_file(path: &Path) -> Result<DynamicInfo, Error> {
+    let source = FileSource::new(std::fs::read(path)?);
+    parse(&source)
+}
+
+/// Parses the main executable of a running process directly out of its
+/// memory, without ever needing the whole image resident at once.
+pub fn parse_pid(pid: u32) -> Result<DynamicInfo, Error> {
+    let source = ProcMemSource::attach(pid)?;
+    parse(&source)
+}
+
+/// Parses an ELF image behind any `ReadAt` source: validates the magic,
+/// reads `EI_CLASS`/`EI_DATA` to pick the right parser, and runs the
+/// full header -> program headers -> dynamic section pipeline, reading
+/// only the slices each step actually needs.
+pub fn parse(source: &dyn ReadAt) -> Result<DynamicInfo, Error> {
+    let ident = source.read_at(0, 6)?;
+    if ident[0..4] != MAGIC_IDENT {
+        return Err(Error::NotAnElf);
+    }
+
+    let endianness = Endianness::from(ident[5]);
+
+    match ident[4] {
+        1 => parse_class::<elf32::Elf32>(source, endianness),
+        2 => parse_class::<elf64::Elf64>(source, endianness),
+        class => Err(Error::UnsupportedClass(class)),
+    }
+}
+
+fn parse_class<T: ElfClass>(source: &dyn ReadAt, endianness: Endianness) -> Result<DynamicInfo, Error> {
+    let sh_meta = T::extract_section_header_meta(source, endianness)?;
+    let program_sec_meta = T::extract_program_section_meta(source, &sh_meta, endianness)?;
+    let criticals = T::read_dynamic_section(source, &program_sec_meta, endianness)?;
+    let needed = T::extract_library_names(source, &criticals)?;
+    let (rpath, runpath) = extract_rpath_runpath(source, &criticals)?;
+    let symbols = T::extract_dynamic_symbols(source, &criticals, endianness)?;
+
+    let note_segments = T::extract_note_segments(source, &sh_meta, endianness)?;
+    let build_id = extract_build_id(source, &note_segments, endianness)?;
+
+    Ok(DynamicInfo {
+        needed,
+        rpath,
+        runpath,
+        build_id,
+        symbols,
+    })
+}
+
+/// GNU's note name for the build-id note (`NT_GNU_BUILD_ID`), including
+/// its terminating NUL as stored on disk.
+const GNU_NOTE_NAME: &[u8] = b"GNU\0";
+/// `n_type` for `NT_GNU_BUILD_ID`.
+const NT_GNU_BUILD_ID: u32 = 3;
+
+/// Walks the note entries of every `PT_NOTE` segment looking for the
+/// GNU build-id (`NT_GNU_BUILD_ID`), returning it as lowercase hex.
+/// A note entry is `n_namesz`/`n_descsz`/`n_type` (each `u32`), followed
+/// by the name padded to 4 bytes and the descriptor padded to 4 bytes.
+fn extract_build_id(
+    source: &dyn ReadAt,
+    note_segments: &[ElfProgramSection],
+    endianness: Endianness,
+) -> Result<Option<String>, Error> {
+    for segment in note_segments {
+        let notes = source.read_at(segment.p_offset, segment.p_filesz as usize)?;
+        let mut pos = 0usize;
+
+        while pos + 12 <= notes.len() {
+            let n_namesz = endianness.read_u32(notes[pos..pos + 4].try_into().unwrap()) as usize;
+            let n_descsz = endianness.read_u32(notes[pos + 4..pos + 8].try_into().unwrap()) as usize;
+            let n_type = endianness.read_u32(notes[pos + 8..pos + 12].try_into().unwrap());
+            pos += 12;
+
+            let name = checked_slice(&notes, pos, n_namesz)?;
+            let name = name.to_vec();
+            pos += align4(n_namesz);
+            let descriptor = checked_slice(&notes, pos, n_descsz)?;
+            let descriptor = descriptor.to_vec();
+            pos += align4(n_descsz);
+
+            if n_type == NT_GNU_BUILD_ID && name == GNU_NOTE_NAME {
+                return Ok(Some(
+                    descriptor.iter().map(|b| format!("{b:02x}")).collect(),
+                ));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn align4(n: usize) -> usize {
+    (n + 3) & !3
+}
+
+/// Resolves `DT_RPATH`/`DT_RUNPATH`, if present, against the same
+/// string table `DT_NEEDED` entries are resolved against.
+fn extract_rpath_runpath(
+    source: &dyn ReadAt,
+    criticals: &DynamicSectionCriticals,
+) -> Result<(Option<String>, Option<String>), Error> {
+    let string_table =
+        source.read_at_absolute(criticals.dt_strtab.d_thing, criticals.dt_strsz as usize)?;
+
+    let rpath = criticals
+        .dt_rpath
+        .as_ref()
+        .map(|e| read_cstr_at(&string_table, e.d_thing))
+        .transpose()?;
+    let runpath = criticals
+        .dt_runpath
+        .as_ref()
+        .map(|e| read_cstr_at(&string_table, e.d_thing))
+        .transpose()?;
+
+    Ok((rpath, runpath))
+}
+
+/// Reads a NUL-terminated string out of a string table at `offset`,
+/// stripping the trailing NUL.
+fn read_cstr_at(string_table: &[u8], offset: u64) -> Result<String, Error> {
+    let mut cursor = Cursor::new(string_table);
+    cursor.seek(SeekFrom::Start(offset)).map_err(|_| Error::OutOfBounds {
+        offset: offset as usize,
+        len: 0,
+        buffer_len: string_table.len(),
+    })?;
+
+    let mut raw = Vec::new();
+    cursor
+        .read_until(0u8, &mut raw)
+        .map_err(|_| Error::NonUtf8LibraryName)?;
+    if raw.last() == Some(&0) {
+        raw.pop();
+    }
+
+    String::from_utf8(raw).map_err(|_| Error::NonUtf8LibraryName)
+}
+
+/// The `EI_DATA` byte (offset 5 of the ELF identification) tells us
+/// whether multi-byte fields are little- or big-endian. Every numeric
+/// field in the header, program headers and dynamic section needs to
+/// be decoded with this in mind, so we read it once in `parse` and
+/// thread it through the parse functions below.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl From<u8> for Endianness {
+    fn from(value: u8) -> Self {
+        match value {
+            2 => Self::Big,
+            // Default to little-endian (EI_DATA == 1) for anything else,
+            // matching how the rest of the parser already treats unknown
+            // bytes leniently rather than failing closed.
+            _ => Self::Little,
+        }
+    }
+}
+
+impl Endianness {
+    pub(crate) fn read_u16(self, bytes: [u8; 2]) -> u16 {
+        match self {
+            Self::Little => u16::from_le_bytes(bytes),
+            Self::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn read_u32(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            Self::Little => u32::from_le_bytes(bytes),
+            Self::Big => u32::from_be_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn read_u64(self, bytes: [u8; 8]) -> u64 {
+        match self {
+            Self::Little => u64::from_le_bytes(bytes),
+            Self::Big => u64::from_be_bytes(bytes),
+        }
+    }
+}
+
+/// Unifies the 32-bit and 64-bit parsers behind a single interface.
+/// `Elf32`/`Elf64` each know their own on-disk field widths and offsets,
+/// but widen everything to `u64` once parsed so the rest of the program
+/// (dynamic section walking, library name extraction) doesn't need to
+/// care which class it's looking at. Every step reads through a
+/// `ReadAt` source rather than an in-memory buffer, so it works equally
+/// well against a file or a live process's memory.
+pub trait ElfClass {
+    /// Extracts the section header meta, crucial for us to read the
+    /// program header table (`e_phoff`, `e_phentsize`, `e_phnum`).
+    fn extract_section_header_meta(
+        source: &dyn ReadAt,
+        endianness: Endianness,
+    ) -> Result<ElfSHeaderMeta, Error>;
+
+    /// Parses every entry of the program header table. Field widths and
+    /// offsets differ between classes, so this is the one step each
+    /// `ElfClass` has to implement itself; everything built on top of it
+    /// (picking out `PT_DYNAMIC`, `PT_NOTE`, ...) is shared.
+    fn parse_program_headers(
+        source: &dyn ReadAt,
+        h_meta: &ElfSHeaderMeta,
+        endianness: Endianness,
+    ) -> Result<Vec<ElfProgramSection>, Error>;
+
+    /// Extract the `PT_DYNAMIC` program section contained in the ELF file.
+    fn extract_program_section_meta(
+        source: &dyn ReadAt,
+        h_meta: &ElfSHeaderMeta,
+        endianness: Endianness,
+    ) -> Result<ElfProgramSection, Error> {
+        Self::parse_program_headers(source, h_meta, endianness)?
+            .into_iter()
+            .find(|sec| sec.p_type == ElfSegmentType::PtDynamic)
+            .ok_or(Error::MissingDynamicSegment)
+    }
+
+    /// Extract every `PT_NOTE` program section contained in the ELF
+    /// file (there can be more than one, e.g. a separate build-id note
+    /// and a separate ABI-tag note).
+    fn extract_note_segments(
+        source: &dyn ReadAt,
+        h_meta: &ElfSHeaderMeta,
+        endianness: Endianness,
+    ) -> Result<Vec<ElfProgramSection>, Error> {
+        Ok(Self::parse_program_headers(source, h_meta, endianness)?
+            .into_iter()
+            .filter(|sec| sec.p_type == ElfSegmentType::PtNote)
+            .collect())
+    }
+
+    /// Reads and extracts the dynamic section criticals which is
+    /// basically the DT_NEEDED entries and DT_STRTAB.
+    fn read_dynamic_section(
+        source: &dyn ReadAt,
+        dyn_meta: &ElfProgramSection,
+        endianness: Endianness,
+    ) -> Result<DynamicSectionCriticals, Error>;
+
+    /// Resolves the DT_NEEDED entries against the string table.
+    /// This is identical for 32 and 64 bit once the criticals are
+    /// widened to `u64`, so it's shared via a default implementation.
+    fn extract_library_names(
+        source: &dyn ReadAt,
+        criticals: &DynamicSectionCriticals,
+    ) -> Result<Vec<String>, Error> {
+        let string_table =
+            source.read_at_absolute(criticals.dt_strtab.d_thing, criticals.dt_strsz as usize)?;
+
+        criticals
+            .dt_needed
+            .iter()
+            .map(|needed| read_cstr_at(&string_table, needed.d_thing))
+            .collect()
+    }
+
+    /// The on-disk size of one `Elf32_Sym`/`Elf64_Sym` entry.
+    const SYM_ENTRY_SIZE: usize;
+
+    /// The width of an address/offset field for this class (4 bytes for
+    /// 32-bit, 8 for 64-bit), needed to walk a `DT_GNU_HASH` bloom filter
+    /// whose entries are address-sized.
+    const ADDR_SIZE: usize;
+
+    /// Decodes one raw `.dynsym` entry. Field widths and ordering differ
+    /// between `Elf32_Sym` and `Elf64_Sym`, so each class implements
+    /// this itself, widening the fields to `u64` the same way the rest
+    /// of the parser does.
+    fn parse_symbol_entry(raw: &[u8], endianness: Endianness) -> RawSymbol;
+
+    /// Walks `.dynsym`, resolving every entry's name through the same
+    /// string table `DT_NEEDED` is resolved against. The dynamic section
+    /// carries no explicit symbol count, so the count is derived from
+    /// `DT_HASH`/`DT_GNU_HASH` first; if the object has neither, there's
+    /// no way to know where the table ends and it's reported as empty.
+    fn extract_dynamic_symbols(
+        source: &dyn ReadAt,
+        criticals: &DynamicSectionCriticals,
+        endianness: Endianness,
+    ) -> Result<Vec<DynamicSymbol>, Error> {
+        let Some(symtab) = &criticals.dt_symtab else {
+            return Ok(Vec::new());
+        };
+
+        let count = symbol_count(source, criticals, endianness, Self::ADDR_SIZE)?;
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        // DT_SYMENT is attacker-controlled; `parse_symbol_entry` indexes
+        // each chunk at fixed offsets up to `Self::SYM_ENTRY_SIZE`, so an
+        // entry size smaller than that (including `0`, which
+        // `[T]::chunks` rejects outright) has to be caught here.
+        if let Some(syment) = criticals.dt_syment {
+            if syment < Self::SYM_ENTRY_SIZE as u64 {
+                return Err(Error::InvalidSymbolEntrySize {
+                    actual: syment,
+                    expected: Self::SYM_ENTRY_SIZE as u64,
+                });
+            }
+        }
+
+        let entry_size = criticals.dt_syment.unwrap_or(Self::SYM_ENTRY_SIZE as u64) as usize;
+        let raw_table = source.read_at_absolute(symtab.d_thing, entry_size * count)?;
+        let string_table =
+            source.read_at_absolute(criticals.dt_strtab.d_thing, criticals.dt_strsz as usize)?;
+
+        // Index 0 is the reserved STN_UNDEF entry: an all-zero, nameless
+        // placeholder every `.dynsym` starts with. `nm -D`/`readelf -sD`
+        // both skip it, so we do too rather than counting a symbol no
+        // one ever sees printed.
+        raw_table
+            .chunks(entry_size)
+            .skip(1)
+            .filter(|chunk| chunk.len() == entry_size)
+            .map(|chunk| {
+                let raw = Self::parse_symbol_entry(chunk, endianness);
+                let name = read_cstr_at(&string_table, raw.st_name as u64)?;
+
+                Ok(DynamicSymbol {
+                    name,
+                    bind: SymbolBind::from(raw.st_info >> 4),
+                    kind: SymbolType::from(raw.st_info & 0xf),
+                    value: raw.st_value,
+                    size: raw.st_size,
+                    imported: raw.st_shndx == 0,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The fields of one `.dynsym` entry we care about, already widened to
+/// `u64` the way `ElfProgramSection`/`DynSectionElement` are.
+pub struct RawSymbol {
+    pub(crate) st_name: u32,
+    pub(crate) st_info: u8,
+    pub(crate) st_shndx: u16,
+    pub(crate) st_value: u64,
+    pub(crate) st_size: u64,
+}
+
+/// One entry of `.dynsym`: a name, what kind of thing it names, and
+/// whether this object imports it (`st_shndx == 0`, i.e. undefined
+/// here) or exports it.
+#[derive(Debug, Clone)]
+pub struct DynamicSymbol {
+    pub name: String,
+    pub bind: SymbolBind,
+    pub kind: SymbolType,
+    pub value: u64,
+    pub size: u64,
+    pub imported: bool,
+}
+
+/// The high nibble of `st_info`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolBind {
+    Local,
+    Global,
+    Weak,
+    Other(u8),
+}
+
+impl From<u8> for SymbolBind {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Local,
+            1 => Self::Global,
+            2 => Self::Weak,
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// The low nibble of `st_info`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolType {
+    NoType,
+    Object,
+    Func,
+    Section,
+    File,
+    Other(u8),
+}
+
+impl From<u8> for SymbolType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::NoType,
+            1 => Self::Object,
+            2 => Self::Func,
+            3 => Self::Section,
+            4 => Self::File,
+            other => Self::Other(other),
+        }
+    }
+}
+
+/// Derives the number of `.dynsym` entries, since the dynamic section
+/// has no tag for it directly.
+///
+/// `DT_HASH`'s header is two `u32`s, `nbucket` then `nchain`; `nchain`
+/// is always exactly the number of dynamic symbols. Lacking that, the
+/// `DT_GNU_HASH` table is walked instead: its header gives `nbuckets`,
+/// `symoffset` (the index of the first symbol covered by the hash) and
+/// `bloom_size` (in address-sized words, so the 4/8 byte addr size of
+/// this class matters here); the highest bucket value is then the
+/// start of the last hash chain, which is followed until an entry with
+/// its low bit set marks the end.
+fn symbol_count(
+    source: &dyn ReadAt,
+    criticals: &DynamicSectionCriticals,
+    endianness: Endianness,
+    addr_size: usize,
+) -> Result<usize, Error> {
+    if let Some(hash) = &criticals.dt_hash {
+        let header = source.read_at_absolute(hash.d_thing, 8)?;
+        let nchain = endianness.read_u32(header[4..8].try_into().unwrap()) as usize;
+        return Ok(nchain);
+    }
+
+    let Some(gnu_hash) = &criticals.dt_gnu_hash else {
+        return Ok(0);
+    };
+
+    let header = source.read_at_absolute(gnu_hash.d_thing, 16)?;
+    let nbuckets = endianness.read_u32(header[0..4].try_into().unwrap()) as usize;
+    let symoffset = endianness.read_u32(header[4..8].try_into().unwrap()) as usize;
+    let bloom_size = endianness.read_u32(header[8..12].try_into().unwrap()) as usize;
+
+    let buckets_offset = gnu_hash.d_thing + 16 + (bloom_size * addr_size) as u64;
+    let buckets = source.read_at_absolute(buckets_offset, nbuckets * 4)?;
+
+    let max_bucket = buckets
+        .chunks(4)
+        .map(|b| endianness.read_u32(b.try_into().unwrap()))
+        .filter(|&b| b != 0)
+        .max();
+
+    let Some(max_bucket) = max_bucket else {
+        return Ok(symoffset);
+    };
+
+    let chain_start = buckets_offset + (nbuckets * 4) as u64;
+    let mut index = max_bucket as usize;
+
+    loop {
+        let entry = source.read_at_absolute(chain_start + ((index - symoffset) * 4) as u64, 4)?;
+        let hash_value = endianness.read_u32(entry.as_slice().try_into().unwrap());
+        index += 1;
+
+        if hash_value & 1 != 0 {
+            break;
+        }
+    }
+
+    Ok(index)
+}
+
+/// Represents the section header metadata which is only crucial for
+/// our task, which is e_phoff, e_phentsize and e_phnum, where e_phoff
+/// represents the offset from the beggining of the file to the program
+/// header table, e_phentsize the size of a single program header entry
+/// and e_phnum the amount of entries.
+///
+/// Widened to `u64` even though 32-bit `e_phoff` is natively a `u32`,
+/// so both `Elf32` and `Elf64` converge on the same type after parsing.
+#[derive(Debug)]
+pub struct ElfSHeaderMeta {
+    pub(crate) e_phoff: u64,
+    pub(crate) e_phentsize: u16,
+    pub(crate) e_phnum: u16,
+}
+
+/// Represents a program section which is a part of the section array.
+/// We will only represent the crucial data, widened to `u64` so that
+/// both ELF classes share the same type once parsed.
+#[derive(Debug, Clone)]
+pub struct ElfProgramSection {
+    pub(crate) p_type: ElfSegmentType,
+    pub(crate) p_offset: u64,
+    pub(crate) p_filesz: u64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ElfSegmentType {
+    PtDynamic,
+    /// Auxiliary information, notably the `.note.gnu.build-id` note
+    PtNote,
+    Irrelevant,
+}
+
+impl From<u32> for ElfSegmentType {
+    fn from(value: u32) -> Self {
+        match value {
+            0x02 => Self::PtDynamic,
+            0x04 => Self::PtNote,
+            _ => Self::Irrelevant,
+        }
+    }
+}
+
+/// Represents an entry in the dynamic section.
+/// d_tag is a signed integer (an i32 for 32-bit, an i64 for 64-bit)
+/// representing the type of the entry.
+/// d_thing is basically a union on the original specification and its
+/// usage derives from the d_tag, we can just merge it to a single field
+/// widened to `u64` since that's the 64-bit union's width and the
+/// 32-bit union's value upcasts cleanly into it.
+#[derive(Debug, Clone)]
+pub struct DynSectionElement {
+    pub(crate) d_tag: DynSectionTag,
+    pub(crate) d_thing: u64,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum DynSectionTag {
+    DtNeeded,
+    /// This marks the offset from the beggining of the file
+    /// to the string tab where we will eventually find the libraries
+    /// names
+    DtStrTab,
+    /// The size (in bytes) off string table
+    DtStrSz,
+    /// A `:`-separated, `$ORIGIN`-aware search path, consulted only
+    /// when the binary has no `DT_RUNPATH`
+    DtRpath,
+    /// Like `DtRpath`, but always consulted and given lower priority
+    /// than `LD_LIBRARY_PATH`
+    DtRunpath,
+    /// Offset of the `.dynsym` table
+    DtSymtab,
+    /// Size in bytes of one `.dynsym` entry
+    DtSyment,
+    /// Offset of the ELF (SysV) symbol hash table
+    DtHash,
+    /// Offset of the GNU-style symbol hash table
+    DtGnuHash,
+    Irrelevant,
+}
+
+impl From<u64> for DynSectionTag {
+    fn from(value: u64) -> Self {
+        match value {
+            0x01 => Self::DtNeeded,
+            0x04 => Self::DtHash,
+            0x05 => Self::DtStrTab,
+            0x06 => Self::DtSymtab,
+            0xa => Self::DtStrSz,
+            0xb => Self::DtSyment,
+            0x0f => Self::DtRpath,
+            0x1d => Self::DtRunpath,
+            0x6ffffef5 => Self::DtGnuHash,
+            _ => Self::Irrelevant,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DynamicSectionCriticals {
+    /// The dynamic section element(s) which represent the
+    /// needed libraries
+    pub(crate) dt_needed: Vec<DynSectionElement>,
+    /// The dynamic section element which represents
+    /// the dt_strtab
+    pub(crate) dt_strtab: DynSectionElement,
+    /// The size in bytes of the string table
+    pub(crate) dt_strsz: u64,
+    /// The dynamic section element representing DT_RPATH, if present
+    pub(crate) dt_rpath: Option<DynSectionElement>,
+    /// The dynamic section element representing DT_RUNPATH, if present
+    pub(crate) dt_runpath: Option<DynSectionElement>,
+    /// The dynamic section element representing DT_SYMTAB, if present
+    pub(crate) dt_symtab: Option<DynSectionElement>,
+    /// The size in bytes of one `.dynsym` entry (DT_SYMENT), if present
+    pub(crate) dt_syment: Option<u64>,
+    /// The dynamic section element representing DT_HASH, if present
+    pub(crate) dt_hash: Option<DynSectionElement>,
+    /// The dynamic section element representing DT_GNU_HASH, if present
+    pub(crate) dt_gnu_hash: Option<DynSectionElement>,
+}
+
+/// Picks the tags `DynamicSectionCriticals` cares about out of a dynamic
+/// section's elements. Once `Elf32`/`Elf64` have each decoded their own
+/// `d_tag`/`d_thing` pairs into `elements` (8 vs. 16 bytes per entry,
+/// widened to `u64`), this lookup is class-agnostic, so both
+/// `read_dynamic_section` implementations call this instead of
+/// duplicating it.
+pub(crate) fn build_dynamic_section_criticals(
+    elements: Vec<DynSectionElement>,
+) -> Result<DynamicSectionCriticals, Error> {
+    let dt_strtab = elements
+        .iter()
+        .find(|x| x.d_tag == DynSectionTag::DtStrTab)
+        .cloned()
+        .ok_or(Error::MissingStrTab)?;
+
+    let dt_strsz = elements
+        .iter()
+        .find(|x| x.d_tag == DynSectionTag::DtStrSz)
+        .cloned()
+        .ok_or(Error::MissingStrSz)?;
+
+    let dt_needed = elements
+        .iter()
+        .filter(|x| x.d_tag == DynSectionTag::DtNeeded)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let dt_rpath = elements
+        .iter()
+        .find(|x| x.d_tag == DynSectionTag::DtRpath)
+        .cloned();
+
+    let dt_runpath = elements
+        .iter()
+        .find(|x| x.d_tag == DynSectionTag::DtRunpath)
+        .cloned();
+
+    let dt_symtab = elements
+        .iter()
+        .find(|x| x.d_tag == DynSectionTag::DtSymtab)
+        .cloned();
+
+    let dt_syment = elements
+        .iter()
+        .find(|x| x.d_tag == DynSectionTag::DtSyment)
+        .map(|x| x.d_thing);
+
+    let dt_hash = elements
+        .iter()
+        .find(|x| x.d_tag == DynSectionTag::DtHash)
+        .cloned();
+
+    let dt_gnu_hash = elements
+        .iter()
+        .find(|x| x.d_tag == DynSectionTag::DtGnuHash)
+        .cloned();
+
+    Ok(DynamicSectionCriticals {
+        dt_strtab,
+        dt_strsz: dt_strsz.d_thing,
+        dt_needed,
+        dt_rpath,
+        dt_runpath,
+        dt_symtab,
+        dt_syment,
+        dt_hash,
+        dt_gnu_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::FileSource;
+
+    fn criticals_with(
+        dt_hash: Option<DynSectionElement>,
+        dt_gnu_hash: Option<DynSectionElement>,
+    ) -> DynamicSectionCriticals {
+        DynamicSectionCriticals {
+            dt_needed: vec![],
+            dt_strtab: DynSectionElement {
+                d_tag: DynSectionTag::DtStrTab,
+                d_thing: 0,
+            },
+            dt_strsz: 0,
+            dt_rpath: None,
+            dt_runpath: None,
+            dt_symtab: None,
+            dt_syment: None,
+            dt_hash,
+            dt_gnu_hash,
+        }
+    }
+
+    #[test]
+    fn symbol_count_reads_nchain_straight_out_of_dt_hash() {
+        let mut buf = vec![0u8; 8];
+        buf[4..8].copy_from_slice(&7u32.to_le_bytes());
+        let source = FileSource::new(buf);
+        let criticals = criticals_with(
+            Some(DynSectionElement {
+                d_tag: DynSectionTag::DtHash,
+                d_thing: 0,
+            }),
+            None,
+        );
+
+        assert_eq!(symbol_count(&source, &criticals, Endianness::Little, 8).unwrap(), 7);
+    }
+
+    #[test]
+    fn symbol_count_walks_the_dt_gnu_hash_chain_to_its_terminator() {
+        // header: nbuckets=1, symoffset=0, bloom_size=1 (bloom_shift unused)
+        let mut buf = vec![0u8; 44];
+        buf[0..4].copy_from_slice(&1u32.to_le_bytes());
+        buf[4..8].copy_from_slice(&0u32.to_le_bytes());
+        buf[8..12].copy_from_slice(&1u32.to_le_bytes());
+        // bloom filter: 1 address-sized (8 byte) word at offset 16, left zeroed
+        // bucket 0, at offset 24, points at chain index 2
+        buf[24..28].copy_from_slice(&2u32.to_le_bytes());
+        // chain: index 2 has its low bit clear (keep going), index 3 has it
+        // set (the chain, and the symbol table, ends here)
+        buf[36..40].copy_from_slice(&0x10u32.to_le_bytes());
+        buf[40..44].copy_from_slice(&0x11u32.to_le_bytes());
+
+        let source = FileSource::new(buf);
+        let criticals = criticals_with(
+            None,
+            Some(DynSectionElement {
+                d_tag: DynSectionTag::DtGnuHash,
+                d_thing: 0,
+            }),
+        );
+
+        assert_eq!(symbol_count(&source, &criticals, Endianness::Little, 8).unwrap(), 4);
+    }
+
+    #[test]
+    fn symbol_count_is_zero_without_either_hash_table() {
+        let source = FileSource::new(vec![]);
+        let criticals = criticals_with(None, None);
+
+        assert_eq!(symbol_count(&source, &criticals, Endianness::Little, 8).unwrap(), 0);
+    }
+}
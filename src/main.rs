@@ -1,60 +1,129 @@
-use std::io::Read;
-
-use imp::elf64::Elf64;
+use error::Error;
+use imp::DynamicInfo;
+use resolver::ResolvedLibrary;
 
+pub mod error;
 pub mod imp;
-
-const MAGIC_IDENT: [u8; 4] = [0x7f, 0x45, 0x4c, 0x46];
+pub mod resolver;
+pub mod source;
 
 // So what should be the workflow of the program?
-// - Open up the ELF file the user wants to analyze and read it to a buffer.
+// - Open up the ELF file the user wants to analyze and read it to a buffer
+//   (or, with --pid, read it directly out of a running process's memory).
 // - Validate and confirm that the file is in fact an ELF file
 //   (We can do so by checking for equivalence with the MAGIC_IDENT)
+// - Read the EI_CLASS byte to know whether we're dealing with a 32-bit
+//   or 64-bit object, and dispatch onto the matching parser
+// - Read the EI_DATA byte to know whether the object is little- or
+//   big-endian, and decode every multi-byte field accordingly
 // - Read the ELF header and extracting the position and size of the program header sections
 // - Read the program header sections locating the dynamic section
 // - Examining the DT_NEEDED entries of the dynamic section
 // - Extracting the offset of the DT_STRTABLE in the ELF file
 // - Reading NEEDED dynamically linked libraries by the program
+// - Recursing into each resolved library to build the full transitive
+//   closure, the way the real `ldd` does
 //
-// ** SUPPORT FOR 64BIT **
+// Malformed or hostile input is expected here, so the parse path never
+// panics: every fallible step returns a `Result`, and `main` is the one
+// place that turns a failure into a diagnostic and a nonzero exit code.
 fn main() {
     let args: Vec<_> = std::env::args().collect();
-    let path = args.get(1);
 
-    if path.is_none() {
-        panic!("Path is missing");
+    let result = match args.get(1).map(String::as_str) {
+        Some("--pid") => match args.get(2).and_then(|s| s.parse::<u32>().ok()) {
+            Some(pid) => run_pid(pid),
+            None => {
+                eprintln!("Usage: ldd-rs --pid <PID>");
+                std::process::exit(1);
+            }
+        },
+        Some(path) => run(path),
+        None => {
+            eprintln!("Usage: ldd-rs <path> | ldd-rs --pid <PID>");
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("ldd-rs: {e}");
+        std::process::exit(1);
     }
+}
 
-    let path = path.unwrap();
+fn run(path: &str) -> Result<(), Error> {
+    let path = std::path::Path::new(path);
+    let info = imp::parse_file(path)?;
+    let origin = path.parent().unwrap_or_else(|| std::path::Path::new("/"));
+    let required = resolver::resolve_from(root_search_info(&info), origin)?;
 
-    let file = std::fs::File::open(path);
+    print_report(&format!("by {}", path.display()), &info, &required);
 
-    if let Err(ref e) = file {
-        eprintln!("Error reading file {e}");
-        return;
-    }
+    Ok(())
+}
+
+fn run_pid(pid: u32) -> Result<(), Error> {
+    let info = imp::parse_pid(pid)?;
+    let exe = std::fs::read_link(format!("/proc/{pid}/exe"))?;
+    let origin = exe.parent().unwrap_or_else(|| std::path::Path::new("/"));
+    let required = resolver::resolve_from(root_search_info(&info), origin)?;
 
-    let mut file = file.unwrap();
-    let mut buf: Vec<u8> = vec![];
+    print_report(&format!("by PID {pid}"), &info, &required);
+
+    Ok(())
+}
 
-    file.read_to_end(&mut buf).unwrap();
-    
-    if &buf[0..4] != &MAGIC_IDENT {
-        panic!("Not an ELF file.");
+/// `resolve_from` only ever looks at `.needed`/`.rpath`/`.runpath`, so
+/// this hands it a copy of just those instead of the whole
+/// `DynamicInfo` — cloning `.symbols` too would duplicate every dynamic
+/// symbol name just to resolve dependencies, when `info` itself is
+/// still right there for `print_report` to borrow afterwards.
+fn root_search_info(info: &DynamicInfo) -> DynamicInfo {
+    DynamicInfo {
+        needed: info.needed.clone(),
+        rpath: info.rpath.clone(),
+        runpath: info.runpath.clone(),
+        build_id: None,
+        symbols: Vec::new(),
     }
-    
-    // This program is yet to support 32 bit programs
-    if buf[4] != 2 || buf[5] != 1 {
-        panic!("Supporting only 64bit objects.");
+}
+
+fn print_report(subject: &str, info: &DynamicInfo, required: &[ResolvedLibrary]) {
+    if let Some(build_id) = &info.build_id {
+        println!("Build ID: {build_id}");
     }
-    
-    let sh_meta = Elf64::extract_section_header_meta(&buf).unwrap();
-    let program_sec_meta = Elf64::extract_program_section_meta(&buf, &sh_meta).unwrap();
-    let dynamic_section_criticals = Elf64::read_dynamic_section(&buf, &program_sec_meta);
-    let required = Elf64::extract_library_names(&buf, &dynamic_section_criticals);
-    
-    println!("Required libraries by {}", path);
+
+    println!("Required libraries {subject}");
     for req in required {
-        println!(" ===> {}", req);
+        match (&req.path, &req.parse_error) {
+            (Some(resolved_path), Some(err)) => {
+                println!(
+                    " ===> {} => {} (unreadable: {err})",
+                    req.soname,
+                    resolved_path.display()
+                )
+            }
+            (Some(resolved_path), None) => {
+                println!(" ===> {} => {}", req.soname, resolved_path.display())
+            }
+            (None, _) => println!(" ===> {} => not found", req.soname),
+        }
+    }
+
+    let (imported, exported): (Vec<_>, Vec<_>) =
+        info.symbols.iter().partition(|sym| sym.imported);
+
+    println!("Imported symbols ({})", imported.len());
+    for sym in &imported {
+        if !sym.name.is_empty() {
+            println!(" U {:?} {:?} {}", sym.bind, sym.kind, sym.name);
+        }
+    }
+
+    println!("Exported symbols ({})", exported.len());
+    for sym in &exported {
+        if !sym.name.is_empty() {
+            println!(" {:016x} {:?} {:?} {}", sym.value, sym.bind, sym.kind, sym.name);
+        }
     }
 }
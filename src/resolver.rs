@@ -0,0 +1,276 @@
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use crate::error::Error;
+use crate::imp::{self, DynamicInfo};
+
+const DEFAULT_SEARCH_PATHS: [&str; 2] = ["/lib", "/usr/lib"];
+const LD_SO_CONF: &str = "/etc/ld.so.conf";
+
+/// One entry of the transitive `DT_NEEDED` closure, resolved against
+/// the same search order the dynamic loader would use.
+pub struct ResolvedLibrary {
+    pub soname: String,
+    pub path: Option<PathBuf>,
+    /// Set when `path` was found on disk but couldn't be parsed as an
+    /// ELF file (e.g. a linker script or some other non-ELF file living
+    /// under that soname). Real `ldd` still reports what it could
+    /// resolve in this case, so a single unreadable dependency can't be
+    /// allowed to discard the rest of the report.
+    pub parse_error: Option<String>,
+}
+
+/// Recursively resolves the transitive closure of `DT_NEEDED` libraries
+/// the way `ldd`/the dynamic loader would: `DT_RPATH` (only when there's
+/// no `DT_RUNPATH`), then `LD_LIBRARY_PATH`, then `DT_RUNPATH`, then the
+/// default search path built from `/etc/ld.so.conf` plus `/lib` and
+/// `/usr/lib`.
+///
+/// Takes the root's already-parsed `DynamicInfo` and `$ORIGIN` rather
+/// than a path or a pid, so callers that already parsed the root (e.g.
+/// `main`, to print its symbols/build-id) don't pay for a second parse
+/// of the same file/process just to resolve its dependencies.
+pub fn resolve_from(root_info: DynamicInfo, origin: &Path) -> Result<Vec<ResolvedLibrary>, Error> {
+    let ld_library_path_dirs: Vec<PathBuf> = std::env::var_os("LD_LIBRARY_PATH")
+        .map(|v| std::env::split_paths(&v).collect())
+        .unwrap_or_default();
+    let conf_paths = read_ld_so_conf(Path::new(LD_SO_CONF));
+
+    // Sonames we've already queued up, so a dependency cycle (or a
+    // diamond in the dependency graph) doesn't make us recurse forever.
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut resolved = Vec::new();
+
+    let mut queue = VecDeque::new();
+    queue.push_back((root_info, origin.to_path_buf()));
+
+    while let Some((info, origin)) = queue.pop_front() {
+        let search_dirs = build_search_order(&info, &origin, &ld_library_path_dirs, &conf_paths);
+
+        for soname in info.needed {
+            if !visited.insert(soname.clone()) {
+                continue;
+            }
+
+            let found = search_library(&soname, &search_dirs);
+            let mut parse_error = None;
+            if let Some(path) = &found {
+                match imp::parse_file(path) {
+                    Ok(next_info) => {
+                        let next_origin =
+                            path.parent().unwrap_or_else(|| Path::new("/")).to_path_buf();
+                        queue.push_back((next_info, next_origin));
+                    }
+                    // A found-but-unparseable library is closer to "not
+                    // found" than to a fatal error: the rest of the
+                    // already-resolved graph is still worth reporting.
+                    Err(e) => parse_error = Some(e.to_string()),
+                }
+            }
+
+            resolved.push(ResolvedLibrary {
+                soname,
+                path: found,
+                parse_error,
+            });
+        }
+    }
+
+    Ok(resolved)
+}
+
+fn build_search_order(
+    info: &DynamicInfo,
+    origin: &Path,
+    ld_library_path_dirs: &[PathBuf],
+    conf_paths: &[PathBuf],
+) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    // DT_RPATH is only honored when there's no DT_RUNPATH.
+    if info.runpath.is_none() {
+        if let Some(rpath) = &info.rpath {
+            dirs.extend(split_path_list(rpath, origin));
+        }
+    }
+
+    dirs.extend(ld_library_path_dirs.iter().cloned());
+
+    if let Some(runpath) = &info.runpath {
+        dirs.extend(split_path_list(runpath, origin));
+    }
+
+    dirs.extend(conf_paths.iter().cloned());
+    dirs.extend(DEFAULT_SEARCH_PATHS.iter().map(PathBuf::from));
+
+    dirs
+}
+
+fn split_path_list(list: &str, origin: &Path) -> Vec<PathBuf> {
+    list.split(':')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| expand_origin(entry, origin))
+        .collect()
+}
+
+/// Expands `$ORIGIN`/`${ORIGIN}` in an rpath/runpath entry to the
+/// directory containing the binary that declared it.
+fn expand_origin(entry: &str, origin: &Path) -> PathBuf {
+    let origin = origin.to_string_lossy();
+    PathBuf::from(
+        entry
+            .replace("${ORIGIN}", &origin)
+            .replace("$ORIGIN", &origin),
+    )
+}
+
+fn search_library(soname: &str, search_dirs: &[PathBuf]) -> Option<PathBuf> {
+    search_dirs.iter().map(|dir| dir.join(soname)).find(|candidate| candidate.is_file())
+}
+
+/// Reads `/etc/ld.so.conf`, following `include` directives (which may
+/// glob, e.g. `include /etc/ld.so.conf.d/*.conf`) the way the dynamic
+/// loader does.
+fn read_ld_so_conf(path: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    read_ld_so_conf_into(path, &mut paths);
+    paths
+}
+
+fn read_ld_so_conf_into(path: &Path, paths: &mut Vec<PathBuf>) {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return;
+    };
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(pattern) = line.strip_prefix("include ") {
+            for included in glob_conf_files(pattern.trim()) {
+                read_ld_so_conf_into(&included, paths);
+            }
+        } else {
+            paths.push(PathBuf::from(line));
+        }
+    }
+}
+
+/// A minimal glob just for `ld.so.conf`'s `include` directive, which in
+/// practice is always a directory plus a single `*`-suffixed filename
+/// pattern (e.g. `/etc/ld.so.conf.d/*.conf`).
+fn glob_conf_files(pattern: &str) -> Vec<PathBuf> {
+    let pattern_path = Path::new(pattern);
+    let (Some(dir), Some(file_pattern)) = (pattern_path.parent(), pattern_path.file_name()) else {
+        return Vec::new();
+    };
+    let file_pattern = file_pattern.to_string_lossy();
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let (prefix, suffix) = file_pattern.split_once('*').unwrap_or((&file_pattern, ""));
+
+    let mut matches: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|candidate| {
+            candidate
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(prefix) && name.ends_with(suffix))
+        })
+        .collect();
+
+    matches.sort();
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info_with(rpath: Option<&str>, runpath: Option<&str>) -> DynamicInfo {
+        DynamicInfo {
+            needed: vec![],
+            rpath: rpath.map(String::from),
+            runpath: runpath.map(String::from),
+            build_id: None,
+            symbols: vec![],
+        }
+    }
+
+    #[test]
+    fn expand_origin_replaces_both_forms() {
+        let origin = Path::new("/opt/app/lib");
+        assert_eq!(
+            expand_origin("$ORIGIN/plugins", origin),
+            PathBuf::from("/opt/app/lib/plugins")
+        );
+        assert_eq!(
+            expand_origin("${ORIGIN}/plugins", origin),
+            PathBuf::from("/opt/app/lib/plugins")
+        );
+    }
+
+    #[test]
+    fn split_path_list_drops_empty_entries_and_expands_origin() {
+        let origin = Path::new("/opt/app");
+        let dirs = split_path_list("/a:$ORIGIN/b::/c", origin);
+        assert_eq!(
+            dirs,
+            vec![
+                PathBuf::from("/a"),
+                PathBuf::from("/opt/app/b"),
+                PathBuf::from("/c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn build_search_order_honors_rpath_when_there_is_no_runpath() {
+        let info = info_with(Some("/rpath"), None);
+        let order = build_search_order(&info, Path::new("/origin"), &[], &[]);
+        assert_eq!(order[0], PathBuf::from("/rpath"));
+    }
+
+    #[test]
+    fn build_search_order_ignores_rpath_once_runpath_is_present() {
+        let info = info_with(Some("/rpath"), Some("/runpath"));
+        let ld_library_path = [PathBuf::from("/ld_path")];
+        let conf_paths = [PathBuf::from("/conf")];
+        let order = build_search_order(&info, Path::new("/origin"), &ld_library_path, &conf_paths);
+
+        // DT_RPATH is dropped entirely once DT_RUNPATH is present, and
+        // LD_LIBRARY_PATH still outranks DT_RUNPATH.
+        assert_eq!(
+            order,
+            vec![
+                PathBuf::from("/ld_path"),
+                PathBuf::from("/runpath"),
+                PathBuf::from("/conf"),
+                PathBuf::from("/lib"),
+                PathBuf::from("/usr/lib"),
+            ]
+        );
+    }
+
+    #[test]
+    fn glob_conf_files_matches_the_suffix_and_sorts() {
+        let dir = std::env::temp_dir().join(format!("ldd-rs-test-glob-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("b.conf"), "").unwrap();
+        std::fs::write(dir.join("a.conf"), "").unwrap();
+        std::fs::write(dir.join("skip.txt"), "").unwrap();
+
+        let pattern = format!("{}/*.conf", dir.display());
+        let matches = glob_conf_files(&pattern);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(matches, vec![dir.join("a.conf"), dir.join("b.conf")]);
+    }
+}